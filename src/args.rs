@@ -39,6 +39,27 @@ pub struct EncodeArgs {
     /// Output file path
     #[arg(short, long)]
     pub output_file: Option<String>,
+
+    /// Keyword to store the message under when the chunk type is a
+    /// standard text chunk (`tEXt`/`zTXt`/`iTXt`).
+    #[arg(short, long)]
+    pub keyword: Option<String>,
+
+    /// DEFLATE-compress the message (always on for `zTXt`, optional for
+    /// `iTXt`, ignored otherwise).
+    #[arg(long)]
+    pub compress: bool,
+
+    /// Split the message across several same-type chunks, each carrying at
+    /// most this many bytes of it after a two-byte part header, so viewers
+    /// that choke on oversized chunks can still read the file.
+    #[arg(long, default_value_t = u32::MAX)]
+    pub max_chunk_size: u32,
+
+    /// Encrypt the message with a key derived from this passphrase before
+    /// embedding it, instead of storing it in the clear.
+    #[arg(short, long)]
+    pub passphrase: Option<String>,
 }
 
 #[derive(Parser, Debug)]
@@ -47,6 +68,14 @@ pub struct DecodeArgs {
     pub file_path: String,
     #[arg(short, long)]
     pub chunk_type: ChunkType,
+
+    /// Keep parsing past CRC-mismatched chunks instead of aborting.
+    #[arg(short, long)]
+    pub recover: bool,
+
+    /// Passphrase to decrypt the message, if it was encoded with one.
+    #[arg(short, long)]
+    pub passphrase: Option<String>,
 }
 
 #[derive(Parser, Debug)]
@@ -61,4 +90,8 @@ pub struct RemoveArgs {
 pub struct PrintArgs {
     #[arg(short, long)]
     pub file_path: String,
+
+    /// Keep parsing past CRC-mismatched chunks instead of aborting.
+    #[arg(short, long)]
+    pub recover: bool,
 }
@@ -3,6 +3,14 @@ use std::{array::TryFromSliceError, fmt::Display};
 
 use crate::chunk_type::{ChunkType, ParseChunkTypeError};
 
+/// Shared CRC-32 table, built once instead of on every chunk construction
+/// or `crc()` call.
+pub(crate) static CRC_ENGINE: crc::Crc<u32> = crc::Crc::<u32>::new(&CRC_32_ISO_HDLC);
+
+pub(crate) fn bytes_to_string(data: &[u8]) -> String {
+    data.iter().map(|&byte| byte as char).collect()
+}
+
 #[derive(Debug)]
 pub struct Chunk {
     chunk_type: ChunkType,
@@ -21,8 +29,16 @@ pub enum ParseChunkError {
     MessageNotFound,
     #[error("Crc not found")]
     CrcNotFound,
-    #[error("Invalid Crc")]
-    InvalidCrc,
+    #[error("Invalid Crc: stored {crc_stored}, computed {crc_computed}")]
+    CrcMismatch {
+        /// Bytes to skip, from the start of this chunk, to resume parsing
+        /// at the next plausible chunk boundary (its length field).
+        recover: usize,
+        crc_stored: u32,
+        crc_computed: u32,
+    },
+    #[error("Invalid PNG signature")]
+    InvalidSignature,
     #[error("Parse slice error")]
     ParseSliceError(#[from] TryFromSliceError),
     #[error("Parse chunk type error")]
@@ -55,16 +71,19 @@ impl TryFrom<&Vec<u8>> for Chunk {
                 .ok_or(ParseChunkError::CrcNotFound)?
                 .try_into()?
         );
-        let checked_crc =
-            crc::Crc::<u32>::new(&CRC_32_ISO_HDLC).checksum(&value[4..(length + 8) as usize]);
+        let checked_crc = CRC_ENGINE.checksum(&value[4..(length + 8) as usize]);
         if crc != checked_crc {
-            return Err(ParseChunkError::InvalidCrc);
+            return Err(ParseChunkError::CrcMismatch {
+                recover: (length + 12) as usize,
+                crc_stored: crc,
+                crc_computed: checked_crc,
+            });
         }
         Ok(Self {
             length,
             chunk_type,
             data: msg,
-            crc: crc,
+            crc,
         })
     }
 }
@@ -83,15 +102,16 @@ impl Display for Chunk {
 
 impl Chunk {
     pub fn new(chunk_type: ChunkType, data: Vec<u8>) -> Chunk {
-        let mut bytes: Vec<u8> = chunk_type.bytes().to_vec();
-        bytes.extend(&data);
         let length = data.len() as u32;
-        let crc = crc::Crc::<u32>::new(&CRC_32_ISO_HDLC).checksum(bytes.as_slice());
+        let mut digest = CRC_ENGINE.digest();
+        digest.update(&chunk_type.bytes());
+        digest.update(&data);
+        let crc = digest.finalize();
         Chunk {
             chunk_type,
             data,
             length,
-            crc: crc,
+            crc,
         }
     }
     pub fn length(&self) -> u32 {
@@ -104,15 +124,7 @@ impl Chunk {
         &self.data
     }
     pub fn crc(&self) -> u32 {
-        let bytes: &[u8] = &[&self.chunk_type.bytes(), self.data.as_slice()].concat();
-        crc::Crc::<u32>::new(&CRC_32_ISO_HDLC).checksum(bytes)
-    }
-    pub fn data_as_string(&self) -> Result<String, anyhow::Error> {
-        Ok(self
-            .data
-            .iter()
-            .map(|&byte| byte as char)
-            .collect::<String>())
+        self.crc
     }
     pub fn as_bytes(&self) -> Vec<u8> {
         self.length
@@ -178,7 +190,7 @@ mod tests {
     #[test]
     fn test_chunk_string() {
         let chunk = testing_chunk();
-        let chunk_string = chunk.data_as_string().unwrap();
+        let chunk_string = bytes_to_string(chunk.data());
         let expected_chunk_string = String::from("This is where your secret message will be!");
         assert_eq!(chunk_string, expected_chunk_string);
     }
@@ -207,7 +219,7 @@ mod tests {
 
         let chunk = Chunk::try_from(chunk_data.as_ref()).unwrap();
 
-        let chunk_string = chunk.data_as_string().unwrap();
+        let chunk_string = bytes_to_string(chunk.data());
         let expected_chunk_string = String::from("This is where your secret message will be!");
 
         assert_eq!(chunk.length(), 42);
@@ -1,6 +1,45 @@
-use std::fs;
+use std::borrow::Cow;
+use std::fs::{self, File};
+use std::io::Read;
 
-use crate::{chunk::Chunk, chunk_type::ChunkType, png::Png};
+use crate::{
+    args::{DecodeArgs, EncodeArgs},
+    chunk::{bytes_to_string, Chunk},
+    chunk_type::ChunkType,
+    crypto,
+    multipart,
+    png::{Decoded, Png, StreamingDecoder},
+    text_chunk,
+};
+
+const STANDARD_TEXT_CHUNK_TYPES: [&str; 3] = ["tEXt", "zTXt", "iTXt"];
+
+fn is_text_chunk_type(chunk_type: &ChunkType) -> bool {
+    STANDARD_TEXT_CHUNK_TYPES.contains(&chunk_type.to_string().as_str())
+}
+
+/// Renders a decoded chunk's raw data as the message a user embedded:
+/// decrypts it first if it's an encrypted container, then unpacks the
+/// standard text-chunk format when the chunk type calls for it.
+fn message_from_data(chunk_type: &ChunkType, data: &[u8], passphrase: Option<&str>) -> String {
+    let data: Cow<[u8]> = if crypto::is_encrypted(data) {
+        let passphrase = passphrase
+            .unwrap_or_else(|| panic!("chunk type \"{}\" is encrypted; pass --passphrase", chunk_type));
+        Cow::Owned(
+            crypto::decrypt(passphrase, data)
+                .unwrap_or_else(|err| panic!("could not decrypt \"{}\": {}", chunk_type, err)),
+        )
+    } else {
+        Cow::Borrowed(data)
+    };
+
+    if is_text_chunk_type(chunk_type) {
+        let (_keyword, text) = text_chunk::parse(chunk_type, &data).unwrap();
+        text
+    } else {
+        bytes_to_string(&data)
+    }
+}
 
 fn read_png(file_path: &str) -> Vec<u8> {
     match fs::read(file_path) {
@@ -9,19 +48,83 @@ fn read_png(file_path: &str) -> Vec<u8> {
     }
 }
 
-pub fn encode(file_path: &str, chunk_type: &ChunkType, message: &str, output: Option<&str>) {
-    let mut png = Png::try_from(read_png(&file_path).as_slice()).unwrap();
-    let new_chunk = Chunk::new(chunk_type.clone(), message.chars().map(|c| c as u8).collect());
-    png.append_chunk(new_chunk);
-    fs::write(output.unwrap_or(&file_path), png.as_bytes()).unwrap();
+pub fn encode(args: &EncodeArgs) {
+    let mut png = Png::try_from(read_png(&args.file_path).as_slice()).unwrap();
+    let data: Vec<u8> = if is_text_chunk_type(&args.chunk_type) {
+        text_chunk::build(&args.chunk_type, args.keyword.as_deref().unwrap_or("pngme"), &args.message, args.compress)
+    } else {
+        args.message.chars().map(|c| c as u8).collect()
+    };
+    let data = match args.passphrase.as_deref() {
+        Some(passphrase) => crypto::encrypt(passphrase, &data).unwrap(),
+        None => data,
+    };
+    for part in multipart::split(&data, args.max_chunk_size as usize).unwrap() {
+        png.append_chunk(Chunk::new(args.chunk_type.clone(), part));
+    }
+    fs::write(args.output_file.as_deref().unwrap_or(&args.file_path), png.as_bytes()).unwrap();
 }
 
-pub fn decode(file_path: &str, chunk_type: &ChunkType) {
-    let png = Png::try_from(read_png(&file_path).as_slice()).unwrap();
-    let Some(chunk) = png.chunk_by_chunk_type(chunk_type) else {
-        panic!();
-    };
-    let message = chunk.data_as_string().unwrap();
+/// Scans the file as a stream, stopping as soon as every part of the
+/// message in `chunk_type` has been decoded instead of buffering the whole
+/// PNG up front. `--recover` trades that guarantee away: it falls back to
+/// the fully-buffered [`Png::try_from_lossy`] so CRC-mismatched chunks
+/// elsewhere in the file don't stop the target from being found, at the
+/// cost of reading the whole file into memory up front.
+pub fn decode(args: &DecodeArgs) {
+    let chunk_type = &args.chunk_type;
+    let passphrase = args.passphrase.as_deref();
+
+    if args.recover {
+        let png = Png::try_from_lossy(read_png(&args.file_path).as_slice()).unwrap();
+        for failure in png.failures() {
+            eprintln!("warning: skipped a corrupted chunk: {}", failure);
+        }
+        let parts = png.chunks_by_chunk_type(chunk_type);
+        if parts.is_empty() {
+            panic!("chunk type \"{}\" not found", chunk_type);
+        }
+        let data = multipart::reassemble(&parts.iter().map(|chunk| chunk.data()).collect::<Vec<_>>()).unwrap();
+        let message = message_from_data(chunk_type, &data, passphrase);
+        println!("message from \"{}\" is \"{}\"", chunk_type, message);
+        return;
+    }
+
+    let mut file = File::open(&args.file_path).unwrap_or_else(|err| panic!("Error while read file: {}", err));
+    let mut decoder = StreamingDecoder::new();
+    let mut read_buf = [0u8; 4096];
+    let mut filled = 0;
+    let mut pos = 0;
+    let mut parts: Vec<Vec<u8>> = Vec::new();
+    let mut expected_parts = None;
+
+    loop {
+        if pos == filled {
+            filled = file.read(&mut read_buf).unwrap();
+            pos = 0;
+            if filled == 0 {
+                panic!("chunk type \"{}\" not found", chunk_type);
+            }
+        }
+        let (consumed, event) = decoder.update(&read_buf[pos..filled]).unwrap();
+        pos += consumed;
+        match event {
+            Decoded::ChunkComplete(_, found_type) if &found_type == chunk_type => {
+                let part = decoder.chunk_data().to_vec();
+                expected_parts = expected_parts.or_else(|| multipart::total_parts(&part));
+                parts.push(part);
+                if Some(parts.len() as u8) == expected_parts {
+                    break;
+                }
+            }
+            Decoded::ImageEnd if parts.is_empty() => panic!("chunk type \"{}\" not found", chunk_type),
+            Decoded::ImageEnd => break,
+            _ => {}
+        }
+    }
+
+    let data = multipart::reassemble(&parts).unwrap();
+    let message = message_from_data(chunk_type, &data, passphrase);
     println!("message from \"{}\" is \"{}\"", chunk_type, message);
 }
 
@@ -31,7 +134,16 @@ pub fn remove(file_path: &str, chunk_type: &ChunkType) {
     fs::write(file_path, png.as_bytes()).unwrap();
 }
 
-pub fn print_png(file_path: &str) {
+pub fn print_png(file_path: &str, recover: bool) {
+    if recover {
+        let png = Png::try_from_lossy(read_png(&file_path).as_slice()).unwrap();
+        for failure in png.failures() {
+            eprintln!("warning: skipped a corrupted chunk: {}", failure);
+        }
+        println!("{}", png);
+        return;
+    }
+
     let png = Png::try_from(read_png(&file_path).as_slice()).unwrap();
     println!("{}", png)
 }
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+
+/// Marks a chunk's data as an encrypted container rather than a plain
+/// message.
+const MAGIC: &[u8; 4] = b"pme1";
+
+const SALT_LEN: usize = 16;
+const SALT_TAG: u8 = 1;
+const NONCE_TAG: u8 = 2;
+const CIPHERTEXT_TAG: u8 = 3;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CryptoError {
+    #[error("chunk data is not an encrypted pngme container")]
+    NotEncrypted,
+    #[error("encrypted container is malformed")]
+    Malformed,
+    #[error("key derivation failed")]
+    KeyDerivation,
+    #[error("decryption failed: wrong passphrase, or the chunk was tampered with")]
+    Tampered,
+}
+
+/// Whether `data` looks like a container produced by [`encrypt`].
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Encrypts `plaintext` under a key derived from `passphrase` and packs the
+/// salt, nonce, and ciphertext (with its authentication tag) into a small
+/// TLV container: `[tag: u8, length: u32 BE, value]` per field.
+pub fn encrypt(passphrase: &str, plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt)?;
+
+    let cipher = Aes256Gcm::new(&key);
+    let mut nonce_bytes = [0u8; 12];
+    OsRng.fill_bytes(&mut nonce_bytes);
+    let nonce = Nonce::from_slice(&nonce_bytes);
+    let ciphertext = cipher
+        .encrypt(nonce, plaintext)
+        .map_err(|_| CryptoError::KeyDerivation)?;
+
+    let mut out = Vec::new();
+    out.extend_from_slice(MAGIC);
+    write_field(&mut out, SALT_TAG, &salt);
+    write_field(&mut out, NONCE_TAG, &nonce_bytes);
+    write_field(&mut out, CIPHERTEXT_TAG, &ciphertext);
+    Ok(out)
+}
+
+/// Verifies and decrypts a container produced by [`encrypt`].
+pub fn decrypt(passphrase: &str, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let data = data.strip_prefix(MAGIC.as_slice()).ok_or(CryptoError::NotEncrypted)?;
+    let fields = parse_fields(data)?;
+    let salt = fields.get(&SALT_TAG).ok_or(CryptoError::Malformed)?;
+    let nonce_bytes = fields.get(&NONCE_TAG).ok_or(CryptoError::Malformed)?;
+    let ciphertext = fields.get(&CIPHERTEXT_TAG).ok_or(CryptoError::Malformed)?;
+    if nonce_bytes.len() != 12 {
+        return Err(CryptoError::Malformed);
+    }
+
+    let key = derive_key(passphrase, salt)?;
+    let cipher = Aes256Gcm::new(&key);
+    let nonce = Nonce::from_slice(nonce_bytes);
+    cipher
+        .decrypt(nonce, ciphertext.as_slice())
+        .map_err(|_| CryptoError::Tampered)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key<Aes256Gcm>, CryptoError> {
+    if salt.len() != SALT_LEN {
+        return Err(CryptoError::Malformed);
+    }
+    let mut key_bytes = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key_bytes)
+        .map_err(|_| CryptoError::KeyDerivation)?;
+    Ok(*Key::<Aes256Gcm>::from_slice(&key_bytes))
+}
+
+fn write_field(out: &mut Vec<u8>, tag: u8, value: &[u8]) {
+    out.push(tag);
+    out.extend_from_slice(&(value.len() as u32).to_be_bytes());
+    out.extend_from_slice(value);
+}
+
+fn parse_fields(mut data: &[u8]) -> Result<HashMap<u8, Vec<u8>>, CryptoError> {
+    let mut fields = HashMap::new();
+    while !data.is_empty() {
+        let tag = *data.first().ok_or(CryptoError::Malformed)?;
+        let len = u32::from_be_bytes(
+            data.get(1..5).ok_or(CryptoError::Malformed)?.try_into().unwrap(),
+        ) as usize;
+        let value = data.get(5..5 + len).ok_or(CryptoError::Malformed)?.to_vec();
+        fields.insert(tag, value);
+        data = &data[5 + len..];
+    }
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt_round_trips() {
+        let ciphertext = encrypt("correct horse battery staple", b"hello world").unwrap();
+        assert!(is_encrypted(&ciphertext));
+        assert_eq!(decrypt("correct horse battery staple", &ciphertext).unwrap(), b"hello world");
+    }
+
+    #[test]
+    fn test_decrypt_fails_with_the_wrong_passphrase() {
+        let ciphertext = encrypt("correct horse battery staple", b"hello world").unwrap();
+        assert!(matches!(decrypt("wrong passphrase", &ciphertext), Err(CryptoError::Tampered)));
+    }
+
+    #[test]
+    fn test_decrypt_fails_on_tampered_ciphertext() {
+        let mut ciphertext = encrypt("correct horse battery staple", b"hello world").unwrap();
+        *ciphertext.last_mut().unwrap() ^= 0xff;
+        assert!(matches!(decrypt("correct horse battery staple", &ciphertext), Err(CryptoError::Tampered)));
+    }
+
+    #[test]
+    fn test_decrypt_rejects_data_without_the_magic_prefix() {
+        assert!(matches!(decrypt("passphrase", b"not encrypted"), Err(CryptoError::NotEncrypted)));
+    }
+
+    #[test]
+    fn test_is_encrypted_checks_the_magic_prefix() {
+        assert!(!is_encrypted(b"plain text"));
+        assert!(is_encrypted(&encrypt("passphrase", b"plain text").unwrap()));
+    }
+
+    #[test]
+    fn test_decrypt_rejects_a_nonce_field_of_the_wrong_length() {
+        let mut container = Vec::new();
+        container.extend_from_slice(MAGIC);
+        write_field(&mut container, SALT_TAG, &[0u8; SALT_LEN]);
+        write_field(&mut container, NONCE_TAG, &[0u8; 5]);
+        write_field(&mut container, CIPHERTEXT_TAG, b"doesn't matter, never reached");
+
+        assert!(matches!(
+            decrypt("correct horse battery staple", &container),
+            Err(CryptoError::Malformed)
+        ));
+    }
+
+    #[test]
+    fn test_decrypt_rejects_a_salt_field_of_the_wrong_length() {
+        let mut container = Vec::new();
+        container.extend_from_slice(MAGIC);
+        write_field(&mut container, SALT_TAG, &[0u8; 3]);
+        write_field(&mut container, NONCE_TAG, &[0u8; 12]);
+        write_field(&mut container, CIPHERTEXT_TAG, b"doesn't matter, never reached");
+
+        assert!(matches!(
+            decrypt("correct horse battery staple", &container),
+            Err(CryptoError::Malformed)
+        ));
+    }
+}
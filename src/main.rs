@@ -1,4 +1,4 @@
-use args::{Cli, EncodeArgs};
+use args::Cli;
 use clap::Parser;
 use commands::{decode, encode, print_png, remove};
 
@@ -6,22 +6,20 @@ mod args;
 mod chunk;
 mod chunk_type;
 mod commands;
+mod crypto;
+mod multipart;
 mod png;
+mod text_chunk;
 
 fn main() {
     let cli = Cli::parse();
 
     match &cli.command {
         Some(command) => match command {
-            args::PngMeArgs::Encode(args) => encode(
-                &args.file_path,
-                &args.chunk_type,
-                &args.message,
-                args.output_file.as_deref(),
-            ),
-            args::PngMeArgs::Decode(args) => decode(&args.file_path, &args.chunk_type),
+            args::PngMeArgs::Encode(args) => encode(args),
+            args::PngMeArgs::Decode(args) => decode(args),
             args::PngMeArgs::Remove(args) => remove(&args.file_path, &args.chunk_type),
-            args::PngMeArgs::Print(args) => print_png(&args.file_path),
+            args::PngMeArgs::Print(args) => print_png(&args.file_path, args.recover),
         },
         None => {}
     }
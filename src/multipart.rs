@@ -0,0 +1,141 @@
+#[derive(Debug, thiserror::Error)]
+pub enum MultipartError {
+    #[error("chunk data is too short to carry a part header")]
+    MissingHeader,
+    #[error("message needs {parts} parts, more than the 255 a one-byte header can address")]
+    TooManyParts { parts: usize },
+    #[error("part index {index} is out of range for {total} total parts")]
+    InvalidIndex { index: u8, total: u8 },
+    #[error("missing part {index} of {total}")]
+    MissingPart { index: u8, total: u8 },
+}
+
+/// Splits `data` into chunk payloads of at most `max_chunk_size` bytes each,
+/// every payload prefixed with a `[total_parts, part_index]` header so
+/// [`reassemble`] can put them back in order regardless of how they were
+/// read off disk.
+pub fn split(data: &[u8], max_chunk_size: usize) -> Result<Vec<Vec<u8>>, MultipartError> {
+    let max_chunk_size = max_chunk_size.max(1);
+    let parts: Vec<&[u8]> = if data.is_empty() {
+        vec![&[]]
+    } else {
+        data.chunks(max_chunk_size).collect()
+    };
+    if parts.len() > u8::MAX as usize {
+        return Err(MultipartError::TooManyParts { parts: parts.len() });
+    }
+    let total = parts.len() as u8;
+    Ok(parts
+        .into_iter()
+        .enumerate()
+        .map(|(index, payload)| {
+            let mut part = Vec::with_capacity(payload.len() + 2);
+            part.push(total);
+            part.push(index as u8);
+            part.extend_from_slice(payload);
+            part
+        })
+        .collect())
+}
+
+/// The total part count declared by a chunk produced by [`split`].
+pub fn total_parts(part_data: &[u8]) -> Option<u8> {
+    part_data.first().copied()
+}
+
+/// Reassembles chunk data blobs produced by [`split`], in whatever order
+/// they were found in the file, back into the original data.
+pub fn reassemble<D: AsRef<[u8]>>(chunks: &[D]) -> Result<Vec<u8>, MultipartError> {
+    let mut parts: Vec<Option<&[u8]>> = Vec::new();
+    let mut total = 0u8;
+    for chunk in chunks {
+        let data = chunk.as_ref();
+        let (&part_total, rest) = data.split_first().ok_or(MultipartError::MissingHeader)?;
+        let (&index, payload) = rest.split_first().ok_or(MultipartError::MissingHeader)?;
+        total = part_total;
+        if index as usize >= total as usize {
+            return Err(MultipartError::InvalidIndex { index, total });
+        }
+        if parts.len() < total as usize {
+            parts.resize(total as usize, None);
+        }
+        parts[index as usize] = Some(payload);
+    }
+
+    let mut data = Vec::new();
+    for (index, part) in parts.into_iter().enumerate() {
+        let payload = part.ok_or(MultipartError::MissingPart {
+            index: index as u8,
+            total,
+        })?;
+        data.extend_from_slice(payload);
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_headers_a_single_part() {
+        let parts = split(b"hello world", u32::MAX as usize).unwrap();
+        assert_eq!(parts, vec![[&[1, 0][..], b"hello world"].concat()]);
+    }
+
+    #[test]
+    fn test_split_headers_each_part_when_more_than_one() {
+        let parts = split(b"hello world", 4).unwrap();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0], vec![3, 0, b'h', b'e', b'l', b'l']);
+        assert_eq!(parts[1], vec![3, 1, b'o', b' ', b'w', b'o']);
+        assert_eq!(parts[2], vec![3, 2, b'r', b'l', b'd']);
+    }
+
+    #[test]
+    fn test_split_rejects_too_many_parts() {
+        let data = vec![0u8; 256 * 4];
+        assert!(matches!(split(&data, 4), Err(MultipartError::TooManyParts { parts: 256 })));
+    }
+
+    #[test]
+    fn test_reassemble_round_trips_a_single_part() {
+        let parts = split(b"hello world", u32::MAX as usize).unwrap();
+        assert_eq!(reassemble(&parts).unwrap(), b"hello world");
+    }
+
+    #[test]
+    fn test_reassemble_round_trips_multiple_parts_out_of_order() {
+        let mut parts = split(b"hello world", 4).unwrap();
+        parts.swap(0, 2);
+        assert_eq!(reassemble(&parts).unwrap(), b"hello world");
+    }
+
+    #[test]
+    fn test_reassemble_reports_missing_part() {
+        let mut parts = split(b"hello world", 4).unwrap();
+        parts.remove(1);
+        assert!(matches!(
+            reassemble(&parts),
+            Err(MultipartError::MissingPart { index: 1, total: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_reassemble_rejects_out_of_range_index_instead_of_panicking() {
+        // The first header claims part 200 of a 5-part message, an index
+        // past its own declared total, which must be reported as an error
+        // rather than indexing out of bounds.
+        let parts = vec![vec![5u8, 200, 1, 2, 3], vec![3u8, 0, 9, 9]];
+        assert!(matches!(
+            reassemble(&parts),
+            Err(MultipartError::InvalidIndex { index: 200, total: 5 })
+        ));
+    }
+
+    #[test]
+    fn test_total_parts_reads_the_header() {
+        let parts = split(b"hello world", 4).unwrap();
+        assert_eq!(total_parts(&parts[0]), Some(3));
+    }
+}
@@ -0,0 +1,233 @@
+use std::fmt::Display;
+
+use crate::chunk::{Chunk, ParseChunkError};
+use crate::chunk_type::ChunkType;
+
+const STANDARD_HEADER: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+
+#[derive(Debug, thiserror::Error)]
+pub enum ParsePngError {
+    #[error("Header not found")]
+    HeaderNotFound,
+    #[error("Invalid header, expected {:?}", STANDARD_HEADER)]
+    InvalidHeader,
+    #[error("Parse chunk error")]
+    ParseChunkError(#[from] ParseChunkError),
+    #[error("{bytes} bytes of trailing data after IEND")]
+    TrailingData { bytes: usize },
+}
+
+#[derive(Debug)]
+pub struct Png {
+    chunks: Vec<Chunk>,
+    failures: Vec<ParseChunkError>,
+}
+
+impl TryFrom<&[u8]> for Png {
+    type Error = ParsePngError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let header = bytes.get(0..8).ok_or(ParsePngError::HeaderNotFound)?;
+        if header != STANDARD_HEADER {
+            return Err(ParsePngError::InvalidHeader);
+        }
+
+        let mut chunks = Vec::new();
+        let mut remaining = &bytes[8..];
+        while !remaining.is_empty() {
+            let length = u32::from_be_bytes(remaining[0..4].try_into().unwrap());
+            let chunk_end = 12 + length as usize;
+            let chunk = Chunk::try_from(&remaining[0..chunk_end].to_vec())?;
+            let chunk_type = chunk.chunk_type().clone();
+            chunks.push(chunk);
+            remaining = &remaining[chunk_end..];
+            if chunk_type.to_string() == "IEND" {
+                break;
+            }
+        }
+
+        if !remaining.is_empty() {
+            return Err(ParsePngError::TrailingData { bytes: remaining.len() });
+        }
+
+        Ok(Self {
+            chunks,
+            failures: Vec::new(),
+        })
+    }
+}
+
+impl Display for Png {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Png {{",)?;
+        for chunk in &self.chunks {
+            writeln!(f, "  {}", chunk)?;
+        }
+        writeln!(f, "}}",)?;
+        Ok(())
+    }
+}
+
+impl Png {
+    pub fn from_chunks(chunks: Vec<Chunk>) -> Self {
+        Self {
+            chunks,
+            failures: Vec::new(),
+        }
+    }
+
+    /// Parses `bytes` like [`Png::try_from`], but on a CRC mismatch records
+    /// the error, skips the bad chunk and resumes at the next plausible
+    /// chunk boundary instead of aborting.
+    pub fn try_from_lossy(bytes: &[u8]) -> Result<Self, ParsePngError> {
+        let header = bytes.get(0..8).ok_or(ParsePngError::HeaderNotFound)?;
+        if header != STANDARD_HEADER {
+            return Err(ParsePngError::InvalidHeader);
+        }
+
+        let mut chunks = Vec::new();
+        let mut failures = Vec::new();
+        let mut remaining = &bytes[8..];
+        while remaining.len() >= 12 {
+            let length = u32::from_be_bytes(remaining[0..4].try_into().unwrap());
+            let chunk_end = 12 + length as usize;
+            let Some(chunk_bytes) = remaining.get(0..chunk_end) else {
+                break;
+            };
+            match Chunk::try_from(&chunk_bytes.to_vec()) {
+                Ok(chunk) => {
+                    let is_end = chunk.chunk_type().to_string() == "IEND";
+                    chunks.push(chunk);
+                    remaining = &remaining[chunk_end..];
+                    if is_end {
+                        break;
+                    }
+                }
+                Err(err) => {
+                    let recover = match &err {
+                        ParseChunkError::CrcMismatch { recover, .. } => Some(*recover),
+                        _ => None,
+                    };
+                    failures.push(err);
+                    match recover {
+                        Some(recover) => remaining = remaining.get(recover..).unwrap_or(&[]),
+                        None => break,
+                    }
+                }
+            }
+        }
+
+        if !remaining.is_empty() {
+            return Err(ParsePngError::TrailingData { bytes: remaining.len() });
+        }
+
+        Ok(Self { chunks, failures })
+    }
+
+    /// One error per chunk that failed CRC verification while parsed with
+    /// [`Png::try_from_lossy`]. Each error holds the stored and computed
+    /// CRCs only; the failed chunk's type and position are not kept.
+    pub fn failures(&self) -> &[ParseChunkError] {
+        &self.failures
+    }
+
+    /// Adds `chunk`, inserting it before a trailing `IEND` if one is present.
+    pub fn append_chunk(&mut self, chunk: Chunk) {
+        let insert_at = self
+            .chunks
+            .iter()
+            .position(|existing| existing.chunk_type().to_string() == "IEND")
+            .unwrap_or(self.chunks.len());
+        self.chunks.insert(insert_at, chunk);
+    }
+
+    pub fn remove_chunks(&mut self, chunk_type: &ChunkType) {
+        self.chunks.retain(|chunk| chunk.chunk_type() != chunk_type);
+    }
+
+    pub fn header(&self) -> &[u8; 8] {
+        &STANDARD_HEADER
+    }
+
+    pub fn chunks(&self) -> &[Chunk] {
+        &self.chunks
+    }
+
+    pub fn chunk_by_chunk_type(&self, chunk_type: &ChunkType) -> Option<&Chunk> {
+        self.chunks.iter().find(|chunk| chunk.chunk_type() == chunk_type)
+    }
+
+    /// All chunks matching `chunk_type`, in file order.
+    pub fn chunks_by_chunk_type(&self, chunk_type: &ChunkType) -> Vec<&Chunk> {
+        self.chunks.iter().filter(|chunk| chunk.chunk_type() == chunk_type).collect()
+    }
+
+    pub fn as_bytes(&self) -> Vec<u8> {
+        STANDARD_HEADER
+            .iter()
+            .copied()
+            .chain(self.chunks.iter().flat_map(|chunk| chunk.as_bytes()))
+            .collect()
+    }
+}
+
+mod decoder;
+pub use decoder::{Decoded, StreamingDecoder};
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn png_with_iend() -> Png {
+        Png::from_chunks(vec![Chunk::new(ChunkType::from_str("IEND").unwrap(), Vec::new())])
+    }
+
+    #[test]
+    fn test_append_chunk_inserts_before_iend() {
+        let mut png = png_with_iend();
+        let message_type = ChunkType::from_str("ruSt").unwrap();
+        png.append_chunk(Chunk::new(message_type.clone(), b"hello world".to_vec()));
+
+        assert_eq!(png.chunks()[0].chunk_type(), &message_type);
+        assert_eq!(png.chunks().last().unwrap().chunk_type().to_string(), "IEND");
+    }
+
+    #[test]
+    fn test_append_chunk_round_trips_through_encode_decode() {
+        let mut png = png_with_iend();
+        let message_type = ChunkType::from_str("ruSt").unwrap();
+        png.append_chunk(Chunk::new(message_type.clone(), b"hello world".to_vec()));
+
+        let reparsed = Png::try_from(png.as_bytes().as_slice()).unwrap();
+        let chunk = reparsed.chunk_by_chunk_type(&message_type).unwrap();
+        assert_eq!(chunk.data(), b"hello world");
+    }
+
+    #[test]
+    fn test_try_from_lossy_recovers_after_crc_mismatch() {
+        let mut png = png_with_iend();
+        let message_type = ChunkType::from_str("ruSt").unwrap();
+        png.append_chunk(Chunk::new(message_type.clone(), b"hello world".to_vec()));
+        let mut bytes = png.as_bytes();
+
+        // Corrupt a byte inside the message chunk's data without touching
+        // its length or type, so the CRC no longer matches.
+        let corrupt_at = 8 + 8 + 4;
+        bytes[corrupt_at] ^= 0xff;
+
+        let recovered = Png::try_from_lossy(&bytes).unwrap();
+        assert_eq!(recovered.failures().len(), 1);
+        assert!(recovered.chunk_by_chunk_type(&message_type).is_none());
+        assert_eq!(recovered.chunks().last().unwrap().chunk_type().to_string(), "IEND");
+    }
+
+    #[test]
+    fn test_try_from_rejects_trailing_data_after_iend() {
+        let mut bytes = png_with_iend().as_bytes();
+        bytes.push(0);
+
+        assert!(matches!(Png::try_from(bytes.as_slice()), Err(ParsePngError::TrailingData { bytes: 1 })));
+    }
+}
@@ -0,0 +1,241 @@
+use crate::chunk::{ParseChunkError, CRC_ENGINE};
+use crate::chunk_type::ChunkType;
+
+const SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+
+/// Parser state for [`StreamingDecoder`], one step per PNG wire field.
+#[derive(Debug)]
+enum State {
+    Signature(u8, [u8; 7]),
+    Length,
+    ChunkType(u32),
+    ChunkData(ChunkType, u32),
+    Crc(ChunkType),
+}
+
+/// An event produced by [`StreamingDecoder::update`] as bytes are fed in.
+#[derive(Debug, PartialEq)]
+pub enum Decoded {
+    /// No event: either not enough bytes were available to complete a step,
+    /// or the step completed a field that has no event of its own (a chunk's
+    /// length or its data).
+    Nothing,
+    /// The 8-byte PNG signature was verified.
+    Header,
+    /// A chunk's length and type have been read; its data is still to come.
+    ChunkBegin(u32, ChunkType),
+    /// A chunk's data was fully read and its CRC verified.
+    ChunkComplete(u32, ChunkType),
+    /// The `IEND` chunk was seen; the stream is finished.
+    ImageEnd,
+}
+
+/// Drives PNG parsing incrementally, without buffering the whole file into
+/// memory.
+pub struct StreamingDecoder {
+    state: Option<State>,
+    length_buf: [u8; 4],
+    length_pos: usize,
+    type_buf: [u8; 4],
+    type_pos: usize,
+    crc_buf: [u8; 4],
+    crc_pos: usize,
+    data_buf: Vec<u8>,
+    digest: crc::Digest<'static, u32>,
+}
+
+impl Default for StreamingDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StreamingDecoder {
+    pub fn new() -> Self {
+        Self {
+            state: Some(State::Signature(0, [0; 7])),
+            length_buf: [0; 4],
+            length_pos: 0,
+            type_buf: [0; 4],
+            type_pos: 0,
+            crc_buf: [0; 4],
+            crc_pos: 0,
+            data_buf: Vec::new(),
+            digest: CRC_ENGINE.digest(),
+        }
+    }
+
+    /// The data of the chunk currently being decoded. Right after
+    /// [`Decoded::ChunkComplete`] this is that chunk's full data; it is
+    /// cleared when the next chunk's type has been read.
+    pub fn chunk_data(&self) -> &[u8] {
+        &self.data_buf
+    }
+
+    /// Feeds `buf` into the decoder, advancing a single wire field. Returns
+    /// the number of bytes consumed and the event produced, if any.
+    pub fn update(&mut self, buf: &[u8]) -> Result<(usize, Decoded), ParseChunkError> {
+        if buf.is_empty() {
+            return Ok((0, Decoded::Nothing));
+        }
+
+        let state = self.state.take().expect("decoder polled after an error");
+        match state {
+            State::Signature(mut matched, mut tail) => {
+                let mut consumed = 0;
+                for &byte in buf {
+                    if matched == 0 && byte != SIGNATURE[0] {
+                        return Err(ParseChunkError::InvalidSignature);
+                    }
+                    consumed += 1;
+                    if matched > 0 {
+                        tail[matched as usize - 1] = byte;
+                    }
+                    matched += 1;
+                    if matched as usize == SIGNATURE.len() {
+                        if tail != SIGNATURE[1..] {
+                            return Err(ParseChunkError::InvalidSignature);
+                        }
+                        self.state = Some(State::Length);
+                        return Ok((consumed, Decoded::Header));
+                    }
+                }
+                self.state = Some(State::Signature(matched, tail));
+                Ok((consumed, Decoded::Nothing))
+            }
+            State::Length => {
+                let consumed = fill(buf, &mut self.length_buf, &mut self.length_pos);
+                if self.length_pos < self.length_buf.len() {
+                    self.state = Some(State::Length);
+                    return Ok((consumed, Decoded::Nothing));
+                }
+                self.length_pos = 0;
+                let length = u32::from_be_bytes(self.length_buf);
+                self.state = Some(State::ChunkType(length));
+                Ok((consumed, Decoded::Nothing))
+            }
+            State::ChunkType(length) => {
+                let consumed = fill(buf, &mut self.type_buf, &mut self.type_pos);
+                if self.type_pos < self.type_buf.len() {
+                    self.state = Some(State::ChunkType(length));
+                    return Ok((consumed, Decoded::Nothing));
+                }
+                self.type_pos = 0;
+                let chunk_type = ChunkType::try_from(self.type_buf)?;
+                self.digest = CRC_ENGINE.digest();
+                self.digest.update(&self.type_buf);
+                self.data_buf.clear();
+                self.state = Some(State::ChunkData(chunk_type.clone(), length));
+                Ok((consumed, Decoded::ChunkBegin(length, chunk_type)))
+            }
+            State::ChunkData(chunk_type, remaining) => {
+                let take = (buf.len() as u32).min(remaining) as usize;
+                self.data_buf.extend_from_slice(&buf[..take]);
+                self.digest.update(&buf[..take]);
+                let remaining = remaining - take as u32;
+                if remaining > 0 {
+                    self.state = Some(State::ChunkData(chunk_type, remaining));
+                    return Ok((take, Decoded::Nothing));
+                }
+                self.state = Some(State::Crc(chunk_type));
+                Ok((take, Decoded::Nothing))
+            }
+            State::Crc(chunk_type) => {
+                let consumed = fill(buf, &mut self.crc_buf, &mut self.crc_pos);
+                if self.crc_pos < self.crc_buf.len() {
+                    self.state = Some(State::Crc(chunk_type));
+                    return Ok((consumed, Decoded::Nothing));
+                }
+                self.crc_pos = 0;
+                let stored_crc = u32::from_be_bytes(self.crc_buf);
+                let computed_crc = std::mem::replace(&mut self.digest, CRC_ENGINE.digest()).finalize();
+                let length = self.data_buf.len() as u32;
+                if stored_crc != computed_crc {
+                    return Err(ParseChunkError::CrcMismatch {
+                        recover: length as usize + 12,
+                        crc_stored: stored_crc,
+                        crc_computed: computed_crc,
+                    });
+                }
+                if chunk_type.to_string() == "IEND" {
+                    self.state = Some(State::Length);
+                    return Ok((consumed, Decoded::ImageEnd));
+                }
+                self.state = Some(State::Length);
+                Ok((consumed, Decoded::ChunkComplete(length, chunk_type)))
+            }
+        }
+    }
+}
+
+/// Copies as much of `buf` as fits into `field` starting at `*pos`,
+/// returning how many bytes were consumed.
+fn fill(buf: &[u8], field: &mut [u8; 4], pos: &mut usize) -> usize {
+    let take = buf.len().min(field.len() - *pos);
+    field[*pos..*pos + take].copy_from_slice(&buf[..take]);
+    *pos += take;
+    take
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::chunk::Chunk;
+
+    fn iend_only_png() -> Vec<u8> {
+        let chunk = Chunk::new(ChunkType::from_str("IEND").unwrap(), Vec::new());
+        SIGNATURE.iter().copied().chain(chunk.as_bytes()).collect()
+    }
+
+    #[test]
+    fn test_update_reports_header_then_image_end() {
+        let bytes = iend_only_png();
+        let mut decoder = StreamingDecoder::new();
+        let mut remaining = bytes.as_slice();
+
+        let (consumed, event) = decoder.update(remaining).unwrap();
+        assert_eq!(event, Decoded::Header);
+        remaining = &remaining[consumed..];
+
+        let mut last_event = Decoded::Nothing;
+        while !remaining.is_empty() {
+            let (consumed, event) = decoder.update(remaining).unwrap();
+            remaining = &remaining[consumed..];
+            last_event = event;
+        }
+        assert_eq!(last_event, Decoded::ImageEnd);
+    }
+
+    #[test]
+    fn test_update_reports_chunk_complete_for_a_data_chunk() {
+        let chunk = Chunk::new(ChunkType::from_str("ruSt").unwrap(), b"hello world".to_vec());
+        let bytes: Vec<u8> = SIGNATURE
+            .iter()
+            .copied()
+            .chain(chunk.as_bytes())
+            .chain(iend_only_png()[8..].iter().copied())
+            .collect();
+
+        let mut decoder = StreamingDecoder::new();
+        let mut remaining = bytes.as_slice();
+        let mut saw_complete = false;
+        while !remaining.is_empty() {
+            let (consumed, event) = decoder.update(remaining).unwrap();
+            remaining = &remaining[consumed..];
+            if let Decoded::ChunkComplete(11, found) = &event {
+                assert_eq!(found.to_string(), "ruSt");
+                assert_eq!(decoder.chunk_data(), b"hello world");
+                saw_complete = true;
+            }
+        }
+        assert!(saw_complete);
+    }
+
+    #[test]
+    fn test_update_rejects_invalid_signature() {
+        let mut decoder = StreamingDecoder::new();
+        assert!(decoder.update(b"not a png").is_err());
+    }
+}
@@ -0,0 +1,167 @@
+use std::io::{Read, Write};
+
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use crate::chunk_type::ChunkType;
+
+/// PNG's "compression method" byte; zero is the only value the spec defines
+/// and it always means zlib/DEFLATE.
+const DEFLATE_METHOD: u8 = 0;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TextChunkError {
+    #[error("text chunk is missing a null-terminated keyword")]
+    MissingKeyword,
+    #[error("text chunk is missing its compression flag and method")]
+    MissingCompressionHeader,
+    #[error("unsupported compression method {0}")]
+    UnsupportedCompressionMethod(u8),
+    #[error("failed to inflate compressed text: {0}")]
+    Decompress(#[from] std::io::Error),
+    #[error("text is not valid UTF-8: {0}")]
+    Utf8(#[from] std::str::Utf8Error),
+}
+
+/// Builds the data payload for a `tEXt`, `zTXt`, or `iTXt` chunk. `compress`
+/// is honored for `iTXt` and implied for `zTXt`; `tEXt` is never compressed.
+pub fn build(chunk_type: &ChunkType, keyword: &str, text: &str, compress: bool) -> Vec<u8> {
+    match chunk_type.to_string().as_str() {
+        "zTXt" => {
+            let mut data = Vec::new();
+            data.extend_from_slice(keyword.as_bytes());
+            data.push(0);
+            data.push(DEFLATE_METHOD);
+            data.extend(deflate(text.as_bytes()));
+            data
+        }
+        "iTXt" => {
+            let mut data = Vec::new();
+            data.extend_from_slice(keyword.as_bytes());
+            data.push(0);
+            data.push(compress as u8);
+            data.push(DEFLATE_METHOD);
+            data.push(0); // language tag
+            data.push(0); // translated keyword
+            if compress {
+                data.extend(deflate(text.as_bytes()));
+            } else {
+                data.extend_from_slice(text.as_bytes());
+            }
+            data
+        }
+        _ => {
+            // tEXt, or anything else: plain keyword + text.
+            let mut data = Vec::new();
+            data.extend_from_slice(keyword.as_bytes());
+            data.push(0);
+            data.extend_from_slice(text.as_bytes());
+            data
+        }
+    }
+}
+
+/// Recovers the keyword and text from a `tEXt`/`zTXt`/`iTXt` payload,
+/// inflating it first if the chunk says it's compressed.
+pub fn parse(chunk_type: &ChunkType, data: &[u8]) -> Result<(String, String), TextChunkError> {
+    let null_at = data.iter().position(|&b| b == 0).ok_or(TextChunkError::MissingKeyword)?;
+    let keyword = std::str::from_utf8(&data[..null_at])?.to_string();
+    let rest = &data[null_at + 1..];
+
+    let text = match chunk_type.to_string().as_str() {
+        "zTXt" => {
+            let (&method, compressed) = rest.split_first().ok_or(TextChunkError::MissingCompressionHeader)?;
+            check_method(method)?;
+            std::str::from_utf8(&inflate(compressed)?)?.to_string()
+        }
+        "iTXt" => {
+            let [compress_flag, method, rest @ ..] = rest else {
+                return Err(TextChunkError::MissingCompressionHeader);
+            };
+            check_method(*method)?;
+            let lang_end = rest.iter().position(|&b| b == 0).ok_or(TextChunkError::MissingKeyword)?;
+            let rest = &rest[lang_end + 1..];
+            let keyword_end = rest.iter().position(|&b| b == 0).ok_or(TextChunkError::MissingKeyword)?;
+            let payload = &rest[keyword_end + 1..];
+            if *compress_flag != 0 {
+                std::str::from_utf8(&inflate(payload)?)?.to_string()
+            } else {
+                std::str::from_utf8(payload)?.to_string()
+            }
+        }
+        _ => rest.iter().map(|&byte| byte as char).collect(),
+    };
+
+    Ok((keyword, text))
+}
+
+fn check_method(method: u8) -> Result<(), TextChunkError> {
+    if method != DEFLATE_METHOD {
+        return Err(TextChunkError::UnsupportedCompressionMethod(method));
+    }
+    Ok(())
+}
+
+fn deflate(bytes: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(bytes).expect("writing to an in-memory buffer cannot fail");
+    encoder.finish().expect("writing to an in-memory buffer cannot fail")
+}
+
+fn inflate(bytes: &[u8]) -> Result<Vec<u8>, std::io::Error> {
+    let mut decoder = ZlibDecoder::new(bytes);
+    let mut out = Vec::new();
+    decoder.read_to_end(&mut out)?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_text_chunk_round_trips() {
+        let chunk_type = ChunkType::from_str("tEXt").unwrap();
+        let data = build(&chunk_type, "Title", "hello world", false);
+        assert_eq!(data, b"Title\0hello world");
+        assert_eq!(parse(&chunk_type, &data).unwrap(), ("Title".to_string(), "hello world".to_string()));
+    }
+
+    #[test]
+    fn test_ztxt_chunk_round_trips() {
+        let chunk_type = ChunkType::from_str("zTXt").unwrap();
+        let data = build(&chunk_type, "Title", "hello world", false);
+        assert_eq!(parse(&chunk_type, &data).unwrap(), ("Title".to_string(), "hello world".to_string()));
+    }
+
+    #[test]
+    fn test_itxt_chunk_round_trips_compressed_and_uncompressed() {
+        let chunk_type = ChunkType::from_str("iTXt").unwrap();
+
+        let uncompressed = build(&chunk_type, "Title", "hello world", false);
+        assert_eq!(parse(&chunk_type, &uncompressed).unwrap(), ("Title".to_string(), "hello world".to_string()));
+
+        let compressed = build(&chunk_type, "Title", "hello world", true);
+        assert_eq!(parse(&chunk_type, &compressed).unwrap(), ("Title".to_string(), "hello world".to_string()));
+    }
+
+    #[test]
+    fn test_parse_rejects_missing_keyword() {
+        let chunk_type = ChunkType::from_str("tEXt").unwrap();
+        assert!(matches!(parse(&chunk_type, b"no null terminator here"), Err(TextChunkError::MissingKeyword)));
+    }
+
+    #[test]
+    fn test_parse_rejects_unsupported_compression_method() {
+        let chunk_type = ChunkType::from_str("zTXt").unwrap();
+        let mut data = b"Title\0".to_vec();
+        data.push(7);
+        assert!(matches!(
+            parse(&chunk_type, &data),
+            Err(TextChunkError::UnsupportedCompressionMethod(7))
+        ));
+    }
+}